@@ -1,15 +1,73 @@
-use std::ops::Deref;
-
+use druid::im::Vector;
 use druid::Data;
 
+/// The four axes a winning run can lie along, expressed as a column/row step.
+///
+/// Only the forward half of each axis is listed; the scan in [`TicTacToe::calc`]
+/// walks both ways, so e.g. `Horizontal` covers left-to-right and right-to-left.
+#[derive(Clone, Copy, Data, Debug, Default, Eq, PartialEq)]
+pub enum Direction {
+    #[default]
+    Horizontal,
+    Vertical,
+    DiagDown,
+    DiagUp
+}
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Horizontal,
+        Direction::Vertical,
+        Direction::DiagDown,
+        Direction::DiagUp
+    ];
+    /// The `(dcol, drow)` step taken when walking this axis forward.
+    #[inline]
+    fn step(self) -> (isize, isize) {
+        match self {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+            Direction::DiagDown => (1, 1),
+            Direction::DiagUp => (1, -1)
+        }
+    }
+}
+
+/// A completed run of matching marks: one end cell, the axis, and how many
+/// cells long the run is. The widget layer draws the strike-through from this.
+#[derive(Clone, Copy, Data, Debug, Default, Eq, PartialEq)]
+pub struct WinLine {
+    pub start: usize,
+    pub dir: Direction,
+    pub len: usize
+}
+
+/// One recorded placement, enough to replay or unwind it.
+///
+/// `player` is the mark that was dropped into `idx`, and `done` carries the
+/// winning run if that placement ended the game (mirroring `TicTacToe::done`).
+#[derive(Clone, Copy, Data, Debug)]
+struct ModifyRecord {
+    idx: usize,
+    player: State,
+    done: Option<WinLine>
+}
+
 #[derive(Clone, Data, Debug)]
 pub struct TicTacToe {
-    cells: [State; 9],
+    cells: Vector<State>,
+    size: usize,
+    win_len: usize,
     state: State,
-    done: Option<u8>
+    done: Option<WinLine>,
+    history: Vector<ModifyRecord>,
+    cursor: usize
 }
 impl TicTacToe {
-    pub fn new(mut first: State) -> Self {
+    pub fn new(first: State) -> Self {
+        Self::new_with(first, 3, 3)
+    }
+    /// Build a `size`×`size` board that is won by `win_len` marks in a row.
+    pub fn new_with(mut first: State, size: usize, win_len: usize) -> Self {
         first = match first {
             State::N if rand::random() => State::X,
             State::N => State::O,
@@ -17,13 +75,25 @@ impl TicTacToe {
         };
 
         Self {
-            cells: [State::N; 9],
+            cells: std::iter::repeat(State::N).take(size * size).collect(),
+            size,
+            win_len,
             state: first,
-            done: None
+            done: None,
+            history: Vector::new(),
+            cursor: 0
         }
     }
     #[inline]
-    pub fn done(&self) -> Option<u8> {
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    #[inline]
+    pub fn win_len(&self) -> usize {
+        self.win_len
+    }
+    #[inline]
+    pub fn done(&self) -> Option<WinLine> {
         self.done
     }
     #[inline]
@@ -34,97 +104,287 @@ impl TicTacToe {
         if self.state != State::N {
             match self.cells.get_mut(idx) {
                 Some(x @ &mut State::N) => {
+                    let player = self.state;
                     *x = self.state;
-                    if let Some((state, orien)) = self.calc(idx) {
-                        self.done = Some(orien);
+                    if let Some((state, line)) = self.calc(idx) {
+                        self.done = Some(line);
                         self.state = state;
                     } else  {
                         self.state.invert();
                     }
 
+                    // A fresh move invalidates any moves that were previously
+                    // undone, so drop the redo tail before recording this one.
+                    self.history.truncate(self.cursor);
+                    self.history.push_back(ModifyRecord { idx, player, done: self.done });
+                    self.cursor += 1;
+
                     true
                 },
                 _ => false
             }
         } else {false}
     }
+    /// Unwind the most recently applied move, clearing its cell and handing the
+    /// turn back to the player who made it. Returns the cell that reverted, or
+    /// `None` when there is nothing left to undo.
+    pub fn undo(&mut self) -> Option<usize> {
+        if self.cursor == 0 {
+            return None;
+        }
+        self.cursor -= 1;
+        let record = self.history[self.cursor];
+        self.cells[record.idx] = State::N;
+        self.state = record.player;
+        self.done = None;
+        Some(record.idx)
+    }
+    /// Replay the next previously-undone move, restoring `state` and `done`.
+    /// Returns the cell that was refilled, or `None` when the redo stack is empty.
+    pub fn redo(&mut self) -> Option<usize> {
+        let record = *self.history.get(self.cursor)?;
+        self.cells[record.idx] = record.player;
+        if let Some(line) = record.done {
+            self.done = Some(line);
+            self.state = record.player;
+        } else {
+            self.state = record.player;
+            self.state.invert();
+        }
+        self.cursor += 1;
+        Some(record.idx)
+    }
     pub fn get(&self, idx: usize) -> State {
         *self.cells.get(idx).unwrap_or(&State::N)
     }
     pub fn draw(&self) -> bool {
         self.done.is_none() && self.cells.iter().all(|x| x != &State::N)
     }
-    pub fn calc(&self, idx: usize) -> Option<(State, u8)> {
-        self.calc_row(Self::row_of(idx))
-            .or_else(|| self.calc_col(Self::col_of(idx)))
-            .or_else(|| match idx {
-                4 => self.calc_dia(false).or_else(|| self.calc_dia(true)),
-                x if x % 4 == 0 => self.calc_dia(false),
-                x if x != 8 && x != 0 && x % 2 == 0 => self.calc_dia(true),
-                _ => None
-            })
-    }
-    fn calc_row(&self, row: usize) -> Option<(State, u8)> {
-        let idx = row * 3;
-        let state = self.cells.get(idx)?;
-        let state2 = self.cells.get(idx + 1)?;
-        if state == state2 && state2 == self.cells.get(idx + 2)? {
-            Some((*state, row as _))
-        } else {
-            None
+    /// Pick the strongest cell for `player` (assumed to be the side to move) by a
+    /// depth-limited minimax search with alpha-beta pruning. Returns `None` when
+    /// the board is already finished or full.
+    ///
+    /// Terminal boards are scored `+(budget - depth)` when `player` wins and
+    /// `-(budget - depth)` when it loses, so nearer wins and later losses are
+    /// preferred; draws score `0`. Pruning keeps the search tractable once the
+    /// board is generalized past 3×3.
+    pub fn ai_move(&self, player: State) -> Option<usize> {
+        if self.done.is_some() || self.draw() {
+            return None;
+        }
+        let budget = self.empties().count() as i32;
+        // A 3×3 board searches to the end; larger boards would blow up, so cap
+        // the lookahead and lean on the neutral heuristic below it.
+        let cap = if self.size <= 3 { i32::MAX } else { 4 };
+        let mut best = None;
+        let mut best_score = i32::MIN;
+        let mut alpha = i32::MIN;
+        let beta = i32::MAX;
+        for idx in self.empties().collect::<Vec<_>>() {
+            let mut next = self.clone();
+            next.set(idx);
+            let score = next.minimax(player, budget, 1, cap, false, alpha, beta);
+            if score > best_score {
+                best_score = score;
+                best = Some(idx);
+            }
+            alpha = alpha.max(best_score);
         }
+        best
     }
-    fn calc_col(&self, col: usize) -> Option<(State, u8)> {
-        let state = self.cells.get(col)?;
-        let state2 = self.cells.get(3 + col)?;
-        if state == state2 && state2 == self.cells.get(6 + col)? {
-            Some((*state, col as u8 + 3))
+    fn minimax(&self, player: State, budget: i32, depth: i32, cap: i32, maximizing: bool, mut alpha: i32, mut beta: i32) -> i32 {
+        if self.done.is_some() {
+            // After a winning placement `state` holds the victor.
+            return if self.state == player { budget - depth } else { -(budget - depth) };
+        }
+        if self.draw() {
+            return 0;
+        }
+        // Past the depth cap, treat the position as neutral rather than search on.
+        if depth >= cap {
+            return 0;
+        }
+
+        if maximizing {
+            let mut value = i32::MIN;
+            for idx in self.empties().collect::<Vec<_>>() {
+                let mut next = self.clone();
+                next.set(idx);
+                value = value.max(next.minimax(player, budget, depth + 1, cap, false, alpha, beta));
+                alpha = alpha.max(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            value
         } else {
-            None
+            let mut value = i32::MAX;
+            for idx in self.empties().collect::<Vec<_>>() {
+                let mut next = self.clone();
+                next.set(idx);
+                value = value.min(next.minimax(player, budget, depth + 1, cap, true, alpha, beta));
+                beta = beta.min(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            value
         }
     }
-    fn calc_dia(&self, right: bool) -> Option<(State, u8)> {
-        if right {
-            let state = self.cells.get(2)?;
-            let state2 = self.cells.get(4)?;
-            if state == state2 && state2 == self.cells.get(6)? {
-                Some((*state, 7))
-            } else {
-                None
+    /// Indices of every still-empty cell, in board order.
+    fn empties(&self) -> impl Iterator<Item = usize> + '_ {
+        self.cells.iter().enumerate().filter(|(_, c)| **c == State::N).map(|(i, _)| i)
+    }
+    /// Evaluate the board around the just-placed cell `idx`. Walks each axis out
+    /// from `idx` in both directions, and reports the first run that reaches
+    /// `win_len` together with the mark that made it.
+    pub fn calc(&self, idx: usize) -> Option<(State, WinLine)> {
+        let mark = self.get(idx);
+        if mark == State::N {
+            return None;
+        }
+        let col = (idx % self.size) as isize;
+        let row = (idx / self.size) as isize;
+        for dir in Direction::ALL {
+            let (dc, dr) = dir.step();
+            // Back up to the start of the run, then sweep forward counting it.
+            let (mut sc, mut sr) = (col, row);
+            while self.mark_at(sc - dc, sr - dr) == Some(mark) {
+                sc -= dc;
+                sr -= dr;
             }
-        } else {
-            let state = self.cells.get(0)?;
-            let state2 = self.cells.get(4)?;
-            if state == state2 && state2 == self.cells.get(8)? {
-                Some((*state, 6))
-            } else {
-                None
+            let start = sr as usize * self.size + sc as usize;
+            let mut len = 0;
+            let (mut cc, mut cr) = (sc, sr);
+            while self.mark_at(cc, cr) == Some(mark) {
+                len += 1;
+                cc += dc;
+                cr += dr;
+            }
+            if len >= self.win_len {
+                return Some((mark, WinLine { start, dir, len }));
             }
         }
+        None
     }
-    #[inline]
-    fn row_of(idx: usize) -> usize {
-        // 0 1 2 3 4 5 6 7 8
-        //   0     1     2
-        idx / 3
+    /// Serialize the position to compact notation: `size/win_len/cells/side`,
+    /// where `cells` is the row-major board (`X`/`O`/`.`) and `side` is the mark
+    /// to move (the winner, on a decided board). Round-trips through [`from_notation`](Self::from_notation).
+    pub fn to_notation(&self) -> String {
+        let cells: String = self.cells.iter().map(|c| match c {
+            State::X => 'X',
+            State::O => 'O',
+            State::N => '.'
+        }).collect();
+        let side = if self.state == State::O { 'O' } else { 'X' };
+        format!("{}/{}/{}/{}", self.size, self.win_len, cells, side)
     }
-    #[inline]
-    fn col_of(idx: usize) -> usize {
-        // 0 3 6 1 4 7 2 5 8
-        //   0     1     2
-        idx % 3
+    /// Parse compact notation back into a board, returning `None` for anything
+    /// malformed or illegal: bad dimensions, wrong cell count, stray characters,
+    /// implausible mark counts, or a position that lies past a decided game.
+    pub fn from_notation(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split('/');
+        let size: usize = parts.next()?.parse().ok()?;
+        let win_len: usize = parts.next()?.parse().ok()?;
+        let cells_str = parts.next()?;
+        let side = match parts.next()? {
+            "X" => State::X,
+            "O" => State::O,
+            _ => return None
+        };
+        if parts.next().is_some() || size == 0 || win_len == 0 || win_len > size {
+            return None;
+        }
+        if cells_str.chars().count() != size * size {
+            return None;
+        }
+
+        let mut cells = Vector::new();
+        let (mut xs, mut os) = (0usize, 0usize);
+        for c in cells_str.chars() {
+            cells.push_back(match c {
+                'X' => { xs += 1; State::X },
+                'O' => { os += 1; State::O },
+                '.' => State::N,
+                _ => return None
+            });
+        }
+
+        // Mark counts must be within one of each other either way.
+        if xs.abs_diff(os) > 1 {
+            return None;
+        }
+
+        let mut game = Self {
+            cells,
+            size,
+            win_len,
+            state: side,
+            done: None,
+            history: Vector::new(),
+            cursor: 0
+        };
+
+        // A board may hold winning runs for at most one mark; if it holds any the
+        // game is over, so record one and make the victor the resident `state`.
+        let mut winner = None;
+        for idx in 0..size * size {
+            if game.get(idx) == State::N {
+                continue;
+            }
+            if let Some((mark, line)) = game.calc(idx) {
+                match winner {
+                    Some((w, _)) if w != mark => return None,
+                    _ => winner = Some((mark, line))
+                }
+            }
+        }
+
+        if let Some((mark, line)) = winner {
+            // Decided board: the winning placement must be the last move, so the
+            // victor holds at least as many marks as the loser (one more if they
+            // moved first, level if second). The side field is not checked
+            // against the counts.
+            let (won, lost) = if mark == State::X { (xs, os) } else { (os, xs) };
+            if won < lost {
+                return None;
+            }
+            game.done = Some(line);
+            game.state = mark;
+        } else {
+            // Live board: it must plausibly be `side`'s turn — they can only be a
+            // mark behind or level, never ahead.
+            let (played, to_move) = if side == State::X { (xs, os) } else { (os, xs) };
+            if played > to_move {
+                return None;
+            }
+        }
+
+        Some(game)
     }
-}
-impl Deref for TicTacToe {
-    type Target = [State; 9];
-    fn deref(&self) -> &Self::Target {
-        &self.cells
+    /// The mark at board coordinates `(col, row)`, or `None` when off-board.
+    fn mark_at(&self, col: isize, row: isize) -> Option<State> {
+        let bound = self.size as isize;
+        if col < 0 || row < 0 || col >= bound || row >= bound {
+            None
+        } else {
+            Some(self.get(row as usize * self.size + col as usize))
+        }
     }
 }
 
 #[derive(Clone, Copy, Data, Debug, Default, Eq, PartialEq)]
 pub enum State {X, O, #[default] N}
 impl State {
+    /// The opposing mark; `N` has no opposite and maps to itself.
+    #[inline]
+    pub fn opposite(self) -> State {
+        match self {
+            State::X => State::O,
+            State::O => State::X,
+            State::N => State::N,
+        }
+    }
     #[inline]
     fn invert(&mut self) {
         *self = match self {
@@ -133,4 +393,104 @@ impl State {
             State::N => State::N,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drop the given marks in order onto a fresh board, returning the game.
+    fn play(first: State, size: usize, win_len: usize, moves: &[usize]) -> TicTacToe {
+        let mut game = TicTacToe::new_with(first, size, win_len);
+        for &idx in moves {
+            game.set(idx);
+        }
+        game
+    }
+
+    #[test]
+    fn detects_horizontal_win() {
+        // X takes the top row while O answers in the middle one.
+        let game = play(State::X, 3, 3, &[0, 3, 1, 4, 2]);
+        let line = game.done().expect("top row should win");
+        assert_eq!(line.dir, Direction::Horizontal);
+        assert_eq!(line.len, 3);
+        assert_eq!(game.state(), State::X);
+    }
+
+    #[test]
+    fn detects_vertical_win() {
+        let game = play(State::X, 3, 3, &[0, 1, 3, 2, 6]);
+        let line = game.done().expect("left column should win");
+        assert_eq!(line.dir, Direction::Vertical);
+        assert_eq!(line.len, 3);
+    }
+
+    #[test]
+    fn detects_diagonal_win() {
+        let game = play(State::X, 3, 3, &[0, 1, 4, 2, 8]);
+        let line = game.done().expect("main diagonal should win");
+        assert_eq!(line.dir, Direction::DiagDown);
+    }
+
+    #[test]
+    fn no_win_on_open_board() {
+        let game = play(State::X, 3, 3, &[0, 1, 2]);
+        assert!(game.done().is_none());
+        assert!(!game.draw());
+    }
+
+    #[test]
+    fn notation_round_trips_open_board() {
+        let game = play(State::X, 3, 3, &[0, 4]);
+        let text = game.to_notation();
+        let back = TicTacToe::from_notation(&text).expect("should parse");
+        assert_eq!(back.to_notation(), text);
+        assert_eq!(back.state(), game.state());
+    }
+
+    #[test]
+    fn notation_round_trips_decided_board() {
+        let game = play(State::X, 3, 3, &[0, 3, 1, 4, 2]);
+        assert!(game.done().is_some());
+        let text = game.to_notation();
+        let back = TicTacToe::from_notation(&text).expect("decided board should parse");
+        assert!(back.done().is_some());
+        assert_eq!(back.state(), State::X);
+        assert_eq!(back.to_notation(), text);
+    }
+
+    #[test]
+    fn ai_takes_immediate_win() {
+        // X to move with two in the top row; completing it wins outright.
+        let game = TicTacToe::from_notation("3/3/XX.OO..../X").expect("legal board");
+        assert_eq!(game.ai_move(State::X), Some(2));
+    }
+
+    #[test]
+    fn ai_blocks_immediate_loss() {
+        // O threatens the top row; X must occupy the open cell to survive.
+        let game = TicTacToe::from_notation("3/3/OO.X...../X").expect("legal board");
+        assert_eq!(game.ai_move(State::X), Some(2));
+    }
+
+    #[test]
+    fn undo_redo_round_trips() {
+        let mut game = play(State::X, 3, 3, &[0, 4]);
+        let before = game.to_notation();
+        assert_eq!(game.undo(), Some(4));
+        assert_eq!(game.get(4), State::N);
+        assert_eq!(game.state(), State::O);
+        assert_eq!(game.redo(), Some(4));
+        assert_eq!(game.to_notation(), before);
+    }
+
+    #[test]
+    fn notation_rejects_malformed() {
+        // Wrong cell count, bad dimensions, stray glyphs, and over-full counts.
+        assert!(TicTacToe::from_notation("3/3/XXX/X").is_none());
+        assert!(TicTacToe::from_notation("0/0/../X").is_none());
+        assert!(TicTacToe::from_notation("3/3/XXZOO..../X").is_none());
+        assert!(TicTacToe::from_notation("3/3/XXXXO..../X").is_none());
+    }
 }
\ No newline at end of file
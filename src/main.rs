@@ -1,8 +1,10 @@
 use druid::widget::Container;
 use druid::{WindowDesc, AppLauncher, Widget};
+use theme::Theme;
 use widget::{Content, AppState};
 
 mod engine;
+mod theme;
 mod widget;
 
 fn main() {
@@ -10,11 +12,14 @@ fn main() {
         .title("TicTacToe")
         .window_size((400.0, 400.0));
 
+    // Load the theme from the working directory, falling back to the dark preset.
+    let theme = Theme::load("config.json5").unwrap_or_default();
+
     // start the application
     AppLauncher::with_window(win)
-        .launch(AppState::default())
+        .launch(AppState::with_theme(theme))
         .expect("Failed to launch application");
 }
 fn build_ui_widget() -> impl Widget<AppState> {
     Container::new(Content::default())
-}
\ No newline at end of file
+}
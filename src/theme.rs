@@ -0,0 +1,252 @@
+use std::path::Path;
+
+use druid::{Color, Data};
+use serde::Deserialize;
+
+/// Easing curves selectable from the config file. Names match the
+/// `keyframe::functions` types they drive.
+#[derive(Clone, Copy, Data, Debug, Default, Eq, PartialEq, Deserialize)]
+pub enum Easing {
+    Linear,
+    EaseInCubic,
+    EaseOutCubic,
+    #[default]
+    EaseInOutQuart
+}
+impl Easing {
+    /// Interpolate `from -> to` at normalized `time` using this curve.
+    pub fn ease(self, from: f64, to: f64, time: f64) -> f64 {
+        use keyframe::functions::{EaseInCubic, EaseInOutQuart, EaseOutCubic, Linear};
+        match self {
+            Easing::Linear => keyframe::ease(Linear, from, to, time),
+            Easing::EaseInCubic => keyframe::ease(EaseInCubic, from, to, time),
+            Easing::EaseOutCubic => keyframe::ease(EaseOutCubic, from, to, time),
+            Easing::EaseInOutQuart => keyframe::ease(EaseInOutQuart, from, to, time)
+        }
+    }
+}
+
+/// Visual configuration: colors, stroke widths, and per-animation easing and
+/// durations (milliseconds). Loaded from a JSON5 file at startup and carried on
+/// `AppState` so the widgets can read it while painting.
+#[derive(Clone, Data, Debug)]
+pub struct Theme {
+    pub state_color: Color,
+    pub hover_color: Color,
+    pub grid_line_color: Color,
+    pub win_line_color: Color,
+    pub bg_color: Color,
+    pub fg_color: Color,
+
+    pub state_width: f64,
+    pub grid_line_width: f64,
+    pub win_line_width: f64,
+
+    pub state_ease: Easing,
+    pub hover_ease: Easing,
+    pub init_ease: Easing,
+    pub done_ease: Easing,
+    pub enter_ease: Easing,
+    pub transition_ease: Easing,
+
+    pub state_dur: f64,
+    pub hover_dur: f64,
+    pub init_dur: f64,
+    pub done_dur: f64,
+    pub enter_dur: f64,
+    pub transition_dur: f64
+}
+impl Theme {
+    /// The dark preset, matching the colors the app originally baked in.
+    pub fn dark() -> Self {
+        Self {
+            state_color: Color::grey8(200),
+            hover_color: Color::grey8(70),
+            grid_line_color: Color::grey8(175),
+            win_line_color: Color::grey8(220),
+            bg_color: Color::grey8(25),
+            fg_color: Color::grey8(210),
+
+            state_width: 7.0,
+            grid_line_width: 5.0,
+            win_line_width: 15.0,
+
+            state_ease: Easing::EaseInOutQuart,
+            hover_ease: Easing::EaseInOutQuart,
+            init_ease: Easing::EaseInCubic,
+            done_ease: Easing::EaseInCubic,
+            enter_ease: Easing::EaseInCubic,
+            transition_ease: Easing::EaseInOutQuart,
+
+            state_dur: 500.0,
+            hover_dur: 100.0,
+            init_dur: 1000.0,
+            done_dur: 500.0,
+            enter_dur: 400.0,
+            transition_dur: 300.0
+        }
+    }
+    /// The light preset: inverted greys, same timings.
+    pub fn light() -> Self {
+        Self {
+            state_color: Color::grey8(40),
+            hover_color: Color::grey8(190),
+            grid_line_color: Color::grey8(90),
+            win_line_color: Color::grey8(20),
+            bg_color: Color::grey8(235),
+            fg_color: Color::grey8(35),
+            ..Self::dark()
+        }
+    }
+    /// Load and parse a JSON5 theme file, falling back to any field the file
+    /// omits. Returns an error when the file is missing or malformed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let text = std::fs::read_to_string(path)?;
+        let cfg: ThemeConfig = json5::from_str(&text)?;
+        Ok(cfg.into_theme())
+    }
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Errors that can surface while loading a theme file.
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(std::io::Error),
+    Parse(json5::Error),
+    Color(String)
+}
+impl From<std::io::Error> for ThemeError {
+    fn from(e: std::io::Error) -> Self {
+        ThemeError::Io(e)
+    }
+}
+impl From<json5::Error> for ThemeError {
+    fn from(e: json5::Error) -> Self {
+        ThemeError::Parse(e)
+    }
+}
+impl std::fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ThemeError::Io(e) => write!(f, "reading theme: {e}"),
+            ThemeError::Parse(e) => write!(f, "parsing theme: {e}"),
+            ThemeError::Color(s) => write!(f, "invalid color: {s}")
+        }
+    }
+}
+impl std::error::Error for ThemeError {}
+
+/// Serde mirror of [`Theme`] using plain, file-friendly types. Every field is
+/// optional so a config may override just the handful of values it cares about.
+#[derive(Deserialize)]
+#[serde(default)]
+struct ThemeConfig {
+    preset: Preset,
+
+    state_color: Option<String>,
+    hover_color: Option<String>,
+    grid_line_color: Option<String>,
+    win_line_color: Option<String>,
+    bg_color: Option<String>,
+    fg_color: Option<String>,
+
+    state_width: Option<f64>,
+    grid_line_width: Option<f64>,
+    win_line_width: Option<f64>,
+
+    state_ease: Option<Easing>,
+    hover_ease: Option<Easing>,
+    init_ease: Option<Easing>,
+    done_ease: Option<Easing>,
+    enter_ease: Option<Easing>,
+    transition_ease: Option<Easing>,
+
+    state_dur: Option<f64>,
+    hover_dur: Option<f64>,
+    init_dur: Option<f64>,
+    done_dur: Option<f64>,
+    enter_dur: Option<f64>,
+    transition_dur: Option<f64>
+}
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        // serde(default) fills omitted fields with this; `None` everywhere means
+        // "inherit from the chosen preset".
+        ThemeConfig {
+            preset: Preset::Dark,
+            state_color: None,
+            hover_color: None,
+            grid_line_color: None,
+            win_line_color: None,
+            bg_color: None,
+            fg_color: None,
+            state_width: None,
+            grid_line_width: None,
+            win_line_width: None,
+            state_ease: None,
+            hover_ease: None,
+            init_ease: None,
+            done_ease: None,
+            enter_ease: None,
+            transition_ease: None,
+            state_dur: None,
+            hover_dur: None,
+            init_dur: None,
+            done_dur: None,
+            enter_dur: None,
+            transition_dur: None
+        }
+    }
+}
+impl ThemeConfig {
+    fn into_theme(self) -> Theme {
+        let mut t = match self.preset {
+            Preset::Dark => Theme::dark(),
+            Preset::Light => Theme::light()
+        };
+        if let Some(c) = self.state_color { t.state_color = parse_color(&c); }
+        if let Some(c) = self.hover_color { t.hover_color = parse_color(&c); }
+        if let Some(c) = self.grid_line_color { t.grid_line_color = parse_color(&c); }
+        if let Some(c) = self.win_line_color { t.win_line_color = parse_color(&c); }
+        if let Some(c) = self.bg_color { t.bg_color = parse_color(&c); }
+        if let Some(c) = self.fg_color { t.fg_color = parse_color(&c); }
+        if let Some(w) = self.state_width { t.state_width = w; }
+        if let Some(w) = self.grid_line_width { t.grid_line_width = w; }
+        if let Some(w) = self.win_line_width { t.win_line_width = w; }
+        if let Some(e) = self.state_ease { t.state_ease = e; }
+        if let Some(e) = self.hover_ease { t.hover_ease = e; }
+        if let Some(e) = self.init_ease { t.init_ease = e; }
+        if let Some(e) = self.done_ease { t.done_ease = e; }
+        if let Some(e) = self.enter_ease { t.enter_ease = e; }
+        if let Some(e) = self.transition_ease { t.transition_ease = e; }
+        if let Some(d) = self.state_dur { t.state_dur = d; }
+        if let Some(d) = self.hover_dur { t.hover_dur = d; }
+        if let Some(d) = self.init_dur { t.init_dur = d; }
+        if let Some(d) = self.done_dur { t.done_dur = d; }
+        if let Some(d) = self.enter_dur { t.enter_dur = d; }
+        if let Some(d) = self.transition_dur { t.transition_dur = d; }
+        t
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Preset {
+    Dark,
+    Light
+}
+
+/// Parse a `#rrggbb` or `#rrggbbaa` hex string, falling back to opaque black.
+fn parse_color(s: &str) -> Color {
+    let hex = s.trim_start_matches('#');
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    match hex.len() {
+        6 => Color::rgb8(byte(0), byte(2), byte(4)),
+        8 => Color::rgba8(byte(0), byte(2), byte(4), byte(6)),
+        _ => Color::BLACK
+    }
+}
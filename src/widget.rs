@@ -4,40 +4,78 @@ use std::ops::Deref;
 use std::time::Duration;
 
 use defaults::Defaults;
-use druid::kurbo::{Line, RoundedRect, Arc};
-use druid::piet::{StrokeStyle, LineJoin, LineCap, StrokeDash};
-use druid::{Widget, Data, Color, RenderContext, Point, Event, WidgetPod, MouseEvent, MouseButton, TimerToken};
-use keyframe::{ease, EasingFunction};
-use keyframe::functions::{EaseInCubic, EaseInOutQuart};
+use druid::kurbo::{Line, RoundedRect, Arc, Affine};
+use druid::piet::{StrokeStyle, LineJoin, LineCap, StrokeDash, Text, TextLayoutBuilder, TextLayout};
+use druid::{Widget, Data, Color, RenderContext, Point, Event, WidgetPod, MouseEvent, MouseButton, TimerToken, KeyEvent, Code, FontFamily, Rect, Application};
+use crate::engine::{Direction, State, TicTacToe, WinLine};
+use crate::theme::{Easing, Theme};
 
-use crate::engine::{State, TicTacToe};
+/// Which screen the root widget is currently showing.
+#[derive(Clone, Copy, Data, Debug, Eq, PartialEq)]
+pub enum Screen {
+    Menu,
+    Game,
+    Results
+}
 
 #[derive(Clone, Data, Defaults)]
 pub struct AppState {
     #[def = "TicTacToe::new(State::N)"]
     game: TicTacToe,
-    anim: f64
+    anim: f64,
+    /// When `Some(side)`, the AI plays `side` and the human plays the other mark.
+    ai: Option<State>,
+    #[def = "Screen::Menu"]
+    screen: Screen,
+    // Pending menu configuration, applied when a game is launched.
+    cfg_first: State,
+    #[def = "3"]
+    cfg_size: usize,
+    #[def = "3"]
+    cfg_win: usize,
+    cfg_ai: bool,
+    cfg_light: bool,
+    #[def = "Theme::default()"]
+    theme: Theme,
+    /// Cell the keyboard cursor / pointer focus is currently on.
+    cursor: usize
+}
+impl AppState {
+    /// Build the initial state with a loaded theme applied.
+    pub fn with_theme(theme: Theme) -> Self {
+        Self { theme, ..Self::default() }
+    }
+    /// Launch a fresh game from the current menu configuration and switch to it.
+    fn start_game(&mut self) {
+        self.game = TicTacToe::new_with(self.cfg_first, self.cfg_size, self.cfg_win);
+        // The AI always takes the mark that moves second.
+        self.ai = self.cfg_ai.then(|| self.game.state().opposite());
+        self.cursor = 0;
+        self.screen = Screen::Game;
+    }
 }
 
-struct Animate<T: Sized, E: EasingFunction, const D: u64> {
+struct Animate<T: Sized> {
     time: f64,
     data: T,
-    ease: E,
-    value: f64
+    ease: Easing,
+    value: f64,
+    dur: f64
 }
-impl<T: Sized, E: EasingFunction, const D: u64> Animate<T, E, D> {
+impl<T: Sized> Animate<T> {
     #[inline]
-    fn new(data: T, ease: E) -> Self {
+    fn new(data: T, ease: Easing, dur: f64) -> Self {
         Self {
             data,
             ease,
             value: 0.0,
-            time: 0.0
+            time: 0.0,
+            dur
         }
     }
     fn anim_frame(&mut self, t: u64) {
-        self.time += t as f64 * 1e-6 / D as f64;
-        self.value = ease::<f64, f64, E>(&self.ease, 0.0, 1.0, self.time);
+        self.time += t as f64 * 1e-6 / self.dur;
+        self.value = self.ease.ease(0.0, 1.0, self.time);
     }
     #[inline]
     fn data(&self) -> &T {
@@ -56,17 +94,7 @@ impl<T: Sized, E: EasingFunction, const D: u64> Animate<T, E, D> {
         self.value >= 1.0
     }
 }
-impl<T: Default + Sized, E: Default + EasingFunction, const D: u64> Default for Animate<T, E, D> {
-    fn default() -> Self {
-        Self {
-            time: 0.0,
-            data: T::default(),
-            ease: E::default(),
-            value: 0.0
-        }
-    }
-}
-impl<T: Sized, E: EasingFunction, const D: u64> Deref for Animate<T, E, D> {
+impl<T: Sized> Deref for Animate<T> {
     type Target = f64;
 
     fn deref(&self) -> &Self::Target {
@@ -74,24 +102,34 @@ impl<T: Sized, E: EasingFunction, const D: u64> Deref for Animate<T, E, D> {
     }
 }
 
-struct ReversibleAnimate<T: Sized, E: EasingFunction, const D: u64> {
+struct ReversibleAnimate<T: Sized> {
     time: f64,
     data: T,
-    ease: E,
+    ease: Easing,
     value: f64,
+    dur: f64,
     reverse: bool
 }
-impl<T: Sized, E: EasingFunction, const D: u64> ReversibleAnimate<T, E, D> {
+impl<T: Sized> ReversibleAnimate<T> {
     #[inline]
-    fn new(data: T, ease: E) -> Self {
+    fn new(data: T, ease: Easing, dur: f64) -> Self {
         Self {
             data,
             ease,
             value: 0.0,
             time: 0.0,
+            dur,
             reverse: false
         }
     }
+    /// Like [`new`](Self::new) but starting from the finished (reversed) end, the
+    /// common case for marks and win lines that animate in from empty.
+    #[inline]
+    fn reversed(data: T, ease: Easing, dur: f64) -> Self {
+        let mut a = Self::new(data, ease, dur);
+        a.reverse();
+        a
+    }
     fn is_reverse(&self) -> bool {
         self.reverse
     }
@@ -100,11 +138,11 @@ impl<T: Sized, E: EasingFunction, const D: u64> ReversibleAnimate<T, E, D> {
     }
     fn anim_frame(&mut self, t: u64) {
         if self.reverse {
-            self.time -= t as f64 * 1e-6 / D as f64;
-            self.value = ease::<f64, f64, E>(&self.ease, 1.0, 0.0, 1.0 - self.time);
+            self.time -= t as f64 * 1e-6 / self.dur;
+            self.value = self.ease.ease(1.0, 0.0, 1.0 - self.time);
         } else {
-            self.time += t as f64 * 1e-6 / D as f64;
-            self.value = ease::<f64, f64, E>(&self.ease, 0.0, 1.0, self.time);
+            self.time += t as f64 * 1e-6 / self.dur;
+            self.value = self.ease.ease(0.0, 1.0, self.time);
         }
     }
     #[inline]
@@ -132,12 +170,7 @@ impl<T: Sized, E: EasingFunction, const D: u64> ReversibleAnimate<T, E, D> {
         }
     }
 }
-impl<T: Default + Sized, E: Default + EasingFunction, const D: u64> Default for ReversibleAnimate<T, E, D> {
-    fn default() -> Self {
-        Self::new(Default::default(), Default::default())
-    }
-}
-impl<T: Sized, E: EasingFunction, const D: u64> Deref for ReversibleAnimate<T, E, D> {
+impl<T: Sized> Deref for ReversibleAnimate<T> {
     type Target = f64;
 
     fn deref(&self) -> &Self::Target {
@@ -146,20 +179,16 @@ impl<T: Sized, E: EasingFunction, const D: u64> Deref for ReversibleAnimate<T, E
 }
 
 pub struct GridCell {
-    idx: u8,
-    state: ReversibleAnimate<bool, EaseInOutQuart, 500>,
-    hover: ReversibleAnimate<(), EaseInOutQuart, 100>
+    idx: usize,
+    state: ReversibleAnimate<bool>,
+    hover: ReversibleAnimate<()>
 }
 impl GridCell {
-    fn new(idx: u8) -> Self {
-        let mut state = ReversibleAnimate::default();
-        state.reverse();
-        let mut hover = ReversibleAnimate::default();
-        hover.reverse();
+    fn new(idx: usize, theme: &Theme) -> Self {
         Self {
             idx,
-            state,
-            hover
+            state: ReversibleAnimate::reversed(false, theme.state_ease, theme.state_dur),
+            hover: ReversibleAnimate::reversed((), theme.hover_ease, theme.hover_dur)
         }
     }
 }
@@ -178,7 +207,9 @@ impl Widget<AppState> for GridCell {
                     ctx.request_anim_frame();
                 }
             },
-            &Event::MouseDown(MouseEvent { button: MouseButton::Left, .. }) if data.game.done().is_none() => {
+            &Event::MouseDown(MouseEvent { button: MouseButton::Left, .. })
+                if data.game.done().is_none() && data.ai != Some(data.game.state()) =>
+            {
                 let state = data.game.state();
                 if data.game.set(self.idx as _) {
                     *self.state.data_mut() = match state {
@@ -193,12 +224,21 @@ impl Widget<AppState> for GridCell {
                 }
             },
             &Event::MouseMove(_) => {
+                // Unify pointer and keyboard focus: hovering a cell moves the
+                // cursor onto it so the highlight follows the mouse.
+                if ctx.is_hot() && data.cursor != self.idx {
+                    data.cursor = self.idx;
+                    ctx.request_update();
+                }
                 hot_change = true;
             }
             _ => ()
         }
-        
-        match (ctx.is_hot(), self.hover.is_reverse()) {
+
+        // The highlight follows the focused cell, whether the focus arrived by
+        // mouse hover or by keyboard navigation.
+        let focused = data.cursor == self.idx;
+        match (focused, self.hover.is_reverse()) {
             (true, false) if !self.state.is_reverse() || data.game.done().is_some() => {
                 self.hover.reverse();
                 ctx.request_anim_frame();
@@ -221,21 +261,46 @@ impl Widget<AppState> for GridCell {
                 self.state.reverse();
                 ctx.request_anim_frame();
             },
+            // A cell filling while the mark animation is still withdrawn means
+            // the move arrived from somewhere other than a click (redo, a pasted
+            // board); drive the placement animation the click path would have.
+            State::X | State::O
+                if old_data.game.get(self.idx as _) == State::N && self.state.is_reverse() =>
+            {
+                *self.state.data_mut() = state == State::O;
+                self.state.reverse();
+                ctx.request_anim_frame();
+            },
+            // The mark changed identity without passing through empty (a pasted
+            // board dropped a different position over this cell). Re-seed the
+            // animation from withdrawn so the new glyph draws in like a placement.
+            State::X | State::O
+                if old_data.game.get(self.idx as _) != State::N
+                    && old_data.game.get(self.idx as _) != state =>
+            {
+                self.state = ReversibleAnimate::reversed(
+                    state == State::O,
+                    data.theme.state_ease,
+                    data.theme.state_dur
+                );
+                self.state.reverse();
+                ctx.request_anim_frame();
+            },
             _ => ()
         }
     }
 
-    fn layout(&mut self, _: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, _: &AppState, _: &druid::Env) -> druid::Size {
-        bc.constrain_aspect_ratio(1.0, bc.max().width / 3.0)
+    fn layout(&mut self, _: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, data: &AppState, _: &druid::Env) -> druid::Size {
+        bc.constrain_aspect_ratio(1.0, bc.max().width / data.game.size() as f64)
     }
 
-    fn paint(&mut self, ctx: &mut druid::PaintCtx, _: &AppState, _: &druid::Env) {
-        const STATE_WIDTH: f64 = 7.0;
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &AppState, _: &druid::Env) {
         const STATE_SCALE: f64 = 0.5;
-        const STATE_COLOR: Color = Color::grey8(200);
         const HOVER_SCALE: f64 = 0.9;
-        const HOVER_COLOR: Color = Color::grey8(70);
         const HOVER_RADII: f64 = 0.1;
+        let state_width = data.theme.state_width;
+        let state_color = &data.theme.state_color;
+        let hover_color = &data.theme.hover_color;
 
         let line_style = StrokeStyle {
             line_join: LineJoin::Round,
@@ -253,17 +318,17 @@ impl Widget<AppState> for GridCell {
             let hover_min = (size - hover_len) / 2.0;
             let hover_max = hover_min + hover_len;
             let shape = RoundedRect::new(hover_min, hover_min, hover_max, hover_max, hover_len * HOVER_RADII);
-            ctx.fill(shape, &HOVER_COLOR.with_alpha(hover));
+            ctx.fill(shape, &hover_color.with_alpha(hover));
         }
-        
+
         if *self.state.data() {
             let len = size * STATE_SCALE * *self.state;
             if len > 0.0 {
                 let off = (size - len) / 2.0;
                 let line1 = Line::new((off, off), (off + len, off + len));
                 let line2 = Line::new((off + len, off), (off, off + len));
-                ctx.stroke_styled(line1, &STATE_COLOR, STATE_WIDTH, &line_style);
-                ctx.stroke_styled(line2, &STATE_COLOR, STATE_WIDTH, &line_style);
+                ctx.stroke_styled(line1, state_color, state_width, &line_style);
+                ctx.stroke_styled(line2, state_color, state_width, &line_style);
             }
         } else {
             let off = size / 2.0;
@@ -275,39 +340,46 @@ impl Widget<AppState> for GridCell {
                 sweep_angle: PI * 2.0 * *self.state,
                 x_rotation: 0.0,
             };
-            ctx.stroke_styled(arc, &STATE_COLOR, STATE_WIDTH, &line_style);
+            ctx.stroke_styled(arc, state_color, state_width, &line_style);
         }
     }
 }
 
 pub struct Grid {
-    init_anim: Animate<(), EaseInCubic, 1000>,
-    done_anim: ReversibleAnimate<u8, EaseInCubic, 500>,
-    cells: [WidgetPod<AppState, GridCell>; 9],
-    timer: TimerToken
+    init_anim: Animate<()>,
+    done_anim: ReversibleAnimate<WinLine>,
+    cells: Vec<WidgetPod<AppState, GridCell>>,
+    size: usize,
+    timer: TimerToken,
+    ai_timer: TimerToken
 }
 impl Default for Grid {
     fn default() -> Self {
-        let mut done_anim = ReversibleAnimate::default();
-        done_anim.reverse();
+        let theme = Theme::default();
         Self {
-            init_anim: Default::default(),
-            done_anim,
+            init_anim: Animate::new((), theme.init_ease, theme.init_dur),
+            done_anim: ReversibleAnimate::reversed(WinLine::default(), theme.done_ease, theme.done_dur),
             timer: TimerToken::INVALID,
-            cells: [
-                WidgetPod::new(GridCell::new(0)),
-                WidgetPod::new(GridCell::new(1)),
-                WidgetPod::new(GridCell::new(2)),
-                WidgetPod::new(GridCell::new(3)),
-                WidgetPod::new(GridCell::new(4)),
-                WidgetPod::new(GridCell::new(5)),
-                WidgetPod::new(GridCell::new(6)),
-                WidgetPod::new(GridCell::new(7)),
-                WidgetPod::new(GridCell::new(8)),
-            ]
+            ai_timer: TimerToken::INVALID,
+            cells: Vec::new(),
+            size: 0
         }
     }
 }
+impl Grid {
+    /// Rebuild the per-cell `WidgetPod`s and replay the entrance animation for a
+    /// new board. Called when the widget is added and whenever a game of a
+    /// different size starts; it re-seeds every animation from the active theme.
+    fn rebuild(&mut self, data: &AppState) {
+        let theme = &data.theme;
+        self.size = data.game.size();
+        self.cells = (0..self.size * self.size)
+            .map(|i| WidgetPod::new(GridCell::new(i, theme)))
+            .collect();
+        self.init_anim = Animate::new((), theme.init_ease, theme.init_dur);
+        self.done_anim = ReversibleAnimate::reversed(WinLine::default(), theme.done_ease, theme.done_dur);
+    }
+}
 impl Widget<AppState> for Grid {
     fn event(&mut self, ctx: &mut druid::EventCtx, event: &Event, data: &mut AppState, env: &druid::Env) {
         match event {
@@ -323,9 +395,81 @@ impl Widget<AppState> for Grid {
                 }
             },
             &Event::Timer(id) if self.timer == id => {
-                data.game = TicTacToe::new(State::N);
+                // Once the win/draw has had a beat to settle, hand off to the
+                // results screen rather than silently wiping the board.
+                data.screen = Screen::Results;
                 ctx.request_update();
             },
+            &Event::Timer(id) if self.ai_timer == id => {
+                if let Some(side) = data.ai {
+                    if data.game.state() == side && data.game.done().is_none() {
+                        if let Some(idx) = data.game.ai_move(side) {
+                            data.game.set(idx);
+                            ctx.request_update();
+                        }
+                    }
+                }
+            },
+            Event::KeyDown(KeyEvent { code: Code::KeyZ, mods, .. }) if mods.ctrl() => {
+                let changed = if mods.shift() {
+                    data.game.redo()
+                } else {
+                    let reverted = data.game.undo();
+                    // In vs-AI mode, undoing the AI's reply lands on the AI's turn,
+                    // which `update` would immediately re-arm into the same move.
+                    // Step back past it so the board returns to the human's turn.
+                    if reverted.is_some() && data.ai == Some(data.game.state()) {
+                        data.game.undo();
+                    }
+                    reverted
+                };
+                if changed.is_some() {
+                    ctx.request_update();
+                    ctx.request_anim_frame();
+                }
+            },
+            Event::KeyDown(KeyEvent { code: Code::KeyC, mods, .. }) if mods.ctrl() => {
+                Application::global().clipboard().put_string(data.game.to_notation());
+            },
+            Event::KeyDown(KeyEvent { code: Code::KeyV, mods, .. }) if mods.ctrl() => {
+                // Ignore clipboard contents that aren't a legal board.
+                if let Some(game) = Application::global().clipboard()
+                    .get_string()
+                    .and_then(|s| TicTacToe::from_notation(&s))
+                {
+                    data.cursor = data.cursor.min(game.size() * game.size() - 1);
+                    data.game = game;
+                    ctx.request_update();
+                    ctx.request_anim_frame();
+                }
+            },
+            Event::KeyDown(KeyEvent { code, mods, .. }) if !mods.ctrl() => {
+                let n = data.game.size();
+                let (mut col, mut row) = (data.cursor % n, data.cursor / n);
+                let last = n.saturating_sub(1);
+                match code {
+                    Code::KeyH | Code::ArrowLeft => col = col.saturating_sub(1),
+                    Code::KeyL | Code::ArrowRight => col = (col + 1).min(last),
+                    Code::KeyK | Code::ArrowUp => row = row.saturating_sub(1),
+                    Code::KeyJ | Code::ArrowDown => row = (row + 1).min(last),
+                    Code::Space | Code::Enter | Code::NumpadEnter => {
+                        if data.game.done().is_none()
+                            && data.ai != Some(data.game.state())
+                            && data.game.set(data.cursor)
+                        {
+                            ctx.request_update();
+                            ctx.request_anim_frame();
+                        }
+                    },
+                    _ => ()
+                }
+                let moved = row * n + col;
+                if moved != data.cursor {
+                    data.cursor = moved;
+                    ctx.request_update();
+                    ctx.request_anim_frame();
+                }
+            },
             _ => ()
         }
 
@@ -335,6 +479,13 @@ impl Widget<AppState> for Grid {
     }
 
     fn lifecycle(&mut self, ctx: &mut druid::LifeCycleCtx, event: &druid::LifeCycle, data: &AppState, env: &druid::Env) {
+        if let druid::LifeCycle::WidgetAdded = event {
+            ctx.register_for_focus();
+            ctx.request_focus();
+            if self.size != data.game.size() {
+                self.rebuild(data);
+            }
+        }
         if self.init_anim.starting() {
             ctx.request_anim_frame();
         }
@@ -345,9 +496,14 @@ impl Widget<AppState> for Grid {
     }
 
     fn update(&mut self, ctx: &mut druid::UpdateCtx, old_data: &AppState, data: &AppState, env: &druid::Env) {
+        if self.size != data.game.size() {
+            self.rebuild(data);
+            ctx.children_changed();
+        }
+
         match data.game.done() {
-            x @ Some(orien) if x != old_data.game.done() => {
-                *self.done_anim.data_mut() = orien;
+            x @ Some(line) if x != old_data.game.done() => {
+                *self.done_anim.data_mut() = line;
                 self.done_anim.reverse();
                 ctx.request_anim_frame();
             },
@@ -362,26 +518,34 @@ impl Widget<AppState> for Grid {
             self.timer = ctx.request_timer(Duration::from_secs(1));
         }
 
+        // When the turn has just flipped to the AI, schedule its reply after a
+        // short beat so the human's placement animation is visible first.
+        let ai_turn = |d: &AppState| matches!(d.ai, Some(s)
+            if d.game.state() == s && d.game.done().is_none() && !d.game.draw());
+        if ai_turn(data) && !ai_turn(old_data) {
+            self.ai_timer = ctx.request_timer(Duration::from_millis(250));
+        }
+
         for e in self.cells.iter_mut() {
             e.update(ctx, data, env);
         }
     }
 
     fn layout(&mut self, ctx: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, data: &AppState, env: &druid::Env) -> druid::Size {
+        let n = self.size;
         for (i, e) in self.cells.iter_mut().enumerate() {
             let size = e.layout(ctx, &bc.loosen(), data, env);
-            e.set_origin(ctx, Point::new((i % 3) as f64 * size.width, (i / 3) as f64 * size.height));
+            e.set_origin(ctx, Point::new((i % n) as f64 * size.width, (i / n) as f64 * size.height));
         }
         bc.constrain_aspect_ratio(1.0, 0.0)
     }
 
     fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &AppState, env: &druid::Env) {
         const GRID_LINE_SCALE: f64 = 0.8;
-        const GRID_LINE_COLOR: Color = Color::grey8(175);
-        const GRID_LINE_WIDTH: f64 = 5.0;
-        const WIN_LINE_SCALE: f64 = 2.0/3.0;
-        const WIN_LINE_WIDTH: f64 = 15.0;
-        const WIN_LINE_COLOR: Color = Color::grey8(220);
+        let grid_line_color = &data.theme.grid_line_color;
+        let grid_line_width = data.theme.grid_line_width;
+        let win_line_color = &data.theme.win_line_color;
+        let win_line_width = data.theme.win_line_width;
 
         let line_style = StrokeStyle {
             line_join: LineJoin::Round,
@@ -390,8 +554,12 @@ impl Widget<AppState> for Grid {
             dash_offset: 0.0
         };
 
+        let bounds = ctx.size().to_rect();
+        ctx.fill(bounds, &data.theme.bg_color);
+
+        let n = self.size;
         let size = ctx.size().width; // width == height, so whatever
-        let grid_padd = size / 3.0;
+        let grid_padd = size / n as f64;
         let grid_line_len = size * GRID_LINE_SCALE * *self.init_anim;
         let grid_line_off = (size - grid_line_len) / 2.0;
 
@@ -399,55 +567,307 @@ impl Widget<AppState> for Grid {
             e.paint(ctx, data, env);
         }
 
-        // Horizontal Grid Lines
-        ctx.stroke_styled(Line {
-            p0: Point::new(grid_line_off, grid_padd),
-            p1: Point::new(grid_line_off + grid_line_len, grid_padd)
-        }, &GRID_LINE_COLOR, GRID_LINE_WIDTH, &line_style);
-        ctx.stroke_styled(Line {
-            p0: Point::new(grid_line_off, grid_padd * 2.0),
-            p1: Point::new(grid_line_off + grid_line_len, grid_padd * 2.0)
-        }, &GRID_LINE_COLOR, GRID_LINE_WIDTH, &line_style);
-
-        // Vertical Grid Lines
-        ctx.stroke_styled(Line {
-            p0: Point::new(grid_padd, grid_line_off),
-            p1: Point::new(grid_padd, grid_line_off + grid_line_len)
-        }, &GRID_LINE_COLOR, GRID_LINE_WIDTH, &line_style);
-        ctx.stroke_styled(Line {
-            p0: Point::new(grid_padd * 2.0, grid_line_off),
-            p1: Point::new(grid_padd * 2.0, grid_line_off + grid_line_len)
-        }, &GRID_LINE_COLOR, GRID_LINE_WIDTH, &line_style);
-
-        // Winning Line
+        // Interior grid lines: n-1 per axis, evenly spaced.
+        for i in 1..n {
+            let off = grid_padd * i as f64;
+            // Horizontal
+            ctx.stroke_styled(Line {
+                p0: Point::new(grid_line_off, off),
+                p1: Point::new(grid_line_off + grid_line_len, off)
+            }, grid_line_color, grid_line_width, &line_style);
+            // Vertical
+            ctx.stroke_styled(Line {
+                p0: Point::new(off, grid_line_off),
+                p1: Point::new(off, grid_line_off + grid_line_len)
+            }, grid_line_color, grid_line_width, &line_style);
+        }
+
+        // Winning Line: grow from the run's start cell along its axis.
         if *self.done_anim > 0.0 {
-            let shape = match *self.done_anim.data() {
-                orien @ 0..=2 => {
-                    let line_len = size * WIN_LINE_SCALE * *self.done_anim;
-                    let line_y = grid_padd * orien as f64 + grid_padd / 2.0;
-                    let line_x = (size - line_len) / 2.0;
-                    Line::new((line_x, line_y), (line_x + line_len, line_y))
-                },
-                orien @ 3..=5 => {
-                    let line_len = size * WIN_LINE_SCALE * *self.done_anim;
-                    let line_x = grid_padd * (orien - 3) as f64 + grid_padd / 2.0;
-                    let line_y = (size - line_len) / 2.0;
-                    Line::new((line_x, line_y), (line_x, line_y + line_len))
-                },
-                6 => {
-                    let line_len = size * WIN_LINE_SCALE * *self.done_anim;
-                    let line_off = (size - line_len) / 2.0;
-                    Line::new((line_off, line_off), (line_off + line_len, line_off + line_len))
-                },
-                7 => {
-                    let line_len = size * WIN_LINE_SCALE * *self.done_anim;
-                    let line_off = (size - line_len) / 2.0;
-                    Line::new((line_off + line_len, line_off), (line_off, line_off + line_len))
-                },
-                _ => unsafe {unreachable_unchecked()}
+            let line = *self.done_anim.data();
+            let (dc, dr) = match line.dir {
+                Direction::Horizontal => (1.0, 0.0),
+                Direction::Vertical => (0.0, 1.0),
+                Direction::DiagDown => (1.0, 1.0),
+                Direction::DiagUp => (1.0, -1.0)
             };
-            ctx.stroke_styled(shape, &WIN_LINE_COLOR, WIN_LINE_WIDTH, &line_style);
+            // Centre of the run's first and (animated) last cell.
+            let sc = (line.start % n) as f64 + 0.5;
+            let sr = (line.start / n) as f64 + 0.5;
+            let reach = (line.len - 1) as f64 * *self.done_anim;
+            let p0 = Point::new(sc * grid_padd, sr * grid_padd);
+            let p1 = Point::new((sc + dc * reach) * grid_padd, (sr + dr * reach) * grid_padd);
+            ctx.stroke_styled(Line::new(p0, p1), win_line_color, win_line_width, &line_style);
         }
-        
+
+    }
+}
+// ---------------------------------------------------------------------------
+// Screen stack: menu, game, and results, with the root widget switching
+// between them. Each screen is a self-contained widget; `Content` owns one
+// `WidgetPod` per screen and slides the active one in on a change.
+// ---------------------------------------------------------------------------
+
+/// Draw `text` centred on `center`, tinted by `alpha`.
+fn draw_centered(ctx: &mut druid::PaintCtx, text: &str, center: Point, color: Color, font: f64, alpha: f64) {
+    let layout = ctx.text()
+        .new_text_layout(text.to_string())
+        .font(FontFamily::SANS_SERIF, font)
+        .text_color(color.with_alpha(alpha))
+        .build()
+        .unwrap();
+    let ts = layout.size();
+    ctx.draw_text(&layout, (center.x - ts.width / 2.0, center.y - ts.height / 2.0));
+}
+
+/// Human-readable label for a starting-symbol choice (`N` means "pick at random").
+fn symbol_label(state: State) -> &'static str {
+    match state {
+        State::X => "X",
+        State::O => "O",
+        State::N => "Random"
+    }
+}
+
+pub struct Menu {
+    enter: Animate<()>,
+    hover: Option<usize>
+}
+impl Default for Menu {
+    fn default() -> Self {
+        let theme = Theme::default();
+        Self { enter: Animate::new((), theme.enter_ease, theme.enter_dur), hover: None }
+    }
+}
+impl Menu {
+    const ROWS: usize = 5;
+    fn row_at(&self, ctx: &druid::EventCtx, y: f64) -> usize {
+        let band = ctx.size().height / Self::ROWS as f64;
+        ((y / band) as usize).min(Self::ROWS - 1)
     }
-}
\ No newline at end of file
+}
+impl Widget<AppState> for Menu {
+    fn event(&mut self, ctx: &mut druid::EventCtx, event: &Event, data: &mut AppState, _: &druid::Env) {
+        match event {
+            &Event::AnimFrame(t) => {
+                ctx.request_paint();
+                if !self.enter.finished() {
+                    self.enter.anim_frame(t);
+                    ctx.request_anim_frame();
+                }
+            },
+            Event::MouseMove(e) => {
+                self.hover = Some(self.row_at(ctx, e.pos.y));
+                ctx.request_paint();
+            },
+            &Event::MouseDown(MouseEvent { button: MouseButton::Left, pos, .. }) => {
+                match self.row_at(ctx, pos.y) {
+                    0 => data.cfg_first = match data.cfg_first {
+                        State::N => State::X,
+                        State::X => State::O,
+                        State::O => State::N
+                    },
+                    1 => {
+                        data.cfg_size = if data.cfg_size >= 5 { 3 } else { data.cfg_size + 1 };
+                        data.cfg_win = data.cfg_size.min(4);
+                    },
+                    2 => data.cfg_ai = !data.cfg_ai,
+                    3 => {
+                        data.cfg_light = !data.cfg_light;
+                        data.theme = if data.cfg_light { Theme::light() } else { Theme::dark() };
+                    },
+                    _ => data.start_game()
+                }
+                ctx.request_update();
+                ctx.request_paint();
+            },
+            _ => ()
+        }
+    }
+    fn lifecycle(&mut self, ctx: &mut druid::LifeCycleCtx, event: &druid::LifeCycle, data: &AppState, _: &druid::Env) {
+        if let druid::LifeCycle::WidgetAdded = event {
+            self.enter = Animate::new((), data.theme.enter_ease, data.theme.enter_dur);
+            ctx.request_anim_frame();
+        }
+    }
+    fn update(&mut self, _: &mut druid::UpdateCtx, _: &AppState, _: &AppState, _: &druid::Env) {}
+    fn layout(&mut self, _: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, _: &AppState, _: &druid::Env) -> druid::Size {
+        bc.max()
+    }
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &AppState, _: &druid::Env) {
+        let size = ctx.size();
+        let alpha = *self.enter;
+        ctx.fill(size.to_rect(), &data.theme.bg_color);
+
+        let band = size.height / Menu::ROWS as f64;
+        let labels = [
+            format!("Start symbol: {}", symbol_label(data.cfg_first)),
+            format!("Board size: {0}x{0}", data.cfg_size),
+            format!("Vs AI: {}", if data.cfg_ai { "on" } else { "off" }),
+            format!("Theme: {}", if data.cfg_light { "Light" } else { "Dark" }),
+            "Start".to_string()
+        ];
+        for (i, label) in labels.iter().enumerate() {
+            let top = band * i as f64;
+            if self.hover == Some(i) {
+                ctx.fill(Rect::new(0.0, top, size.width, top + band), &data.theme.hover_color.with_alpha(alpha));
+            }
+            let font = if i == Menu::ROWS - 1 { 36.0 } else { 24.0 };
+            draw_centered(ctx, label, Point::new(size.width / 2.0, top + band / 2.0), data.theme.fg_color, font, alpha);
+        }
+    }
+}
+
+pub struct Results {
+    enter: Animate<()>,
+    hover: Option<usize>
+}
+impl Default for Results {
+    fn default() -> Self {
+        let theme = Theme::default();
+        Self { enter: Animate::new((), theme.enter_ease, theme.enter_dur), hover: None }
+    }
+}
+impl Results {
+    /// Which of the two bottom buttons (0 = play again, 1 = menu) `pos` is over.
+    fn button_at(ctx: &druid::EventCtx, pos: Point) -> Option<usize> {
+        let size = ctx.size();
+        if pos.y < size.height * 0.6 {
+            return None;
+        }
+        Some(if pos.x < size.width / 2.0 { 0 } else { 1 })
+    }
+}
+impl Widget<AppState> for Results {
+    fn event(&mut self, ctx: &mut druid::EventCtx, event: &Event, data: &mut AppState, _: &druid::Env) {
+        match event {
+            &Event::AnimFrame(t) => {
+                ctx.request_paint();
+                if !self.enter.finished() {
+                    self.enter.anim_frame(t);
+                    ctx.request_anim_frame();
+                }
+            },
+            Event::MouseMove(e) => {
+                self.hover = Results::button_at(ctx, e.pos);
+                ctx.request_paint();
+            },
+            &Event::MouseDown(MouseEvent { button: MouseButton::Left, pos, .. }) => {
+                match Results::button_at(ctx, pos) {
+                    Some(0) => data.start_game(),
+                    Some(1) => data.screen = Screen::Menu,
+                    _ => ()
+                }
+                ctx.request_update();
+                ctx.request_paint();
+            },
+            _ => ()
+        }
+    }
+    fn lifecycle(&mut self, ctx: &mut druid::LifeCycleCtx, event: &druid::LifeCycle, data: &AppState, _: &druid::Env) {
+        if let druid::LifeCycle::WidgetAdded = event {
+            self.enter = Animate::new((), data.theme.enter_ease, data.theme.enter_dur);
+            ctx.request_anim_frame();
+        }
+    }
+    fn update(&mut self, _: &mut druid::UpdateCtx, _: &AppState, _: &AppState, _: &druid::Env) {}
+    fn layout(&mut self, _: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, _: &AppState, _: &druid::Env) -> druid::Size {
+        bc.max()
+    }
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &AppState, _: &druid::Env) {
+        let size = ctx.size();
+        let alpha = *self.enter;
+        ctx.fill(size.to_rect(), &data.theme.bg_color);
+
+        let outcome = match data.game.done() {
+            Some(_) => format!("{} wins!", symbol_label(data.game.state())),
+            None => "Draw".to_string()
+        };
+        draw_centered(ctx, &outcome, Point::new(size.width / 2.0, size.height * 0.3), data.theme.fg_color, 40.0, alpha);
+
+        let labels = ["Play again", "Menu"];
+        for (i, label) in labels.iter().enumerate() {
+            let x0 = size.width / 2.0 * i as f64;
+            if self.hover == Some(i) {
+                ctx.fill(Rect::new(x0, size.height * 0.6, x0 + size.width / 2.0, size.height), &data.theme.hover_color.with_alpha(alpha));
+            }
+            draw_centered(ctx, label, Point::new(x0 + size.width / 4.0, size.height * 0.8), data.theme.fg_color, 26.0, alpha);
+        }
+    }
+}
+
+type Screened = WidgetPod<AppState, Box<dyn Widget<AppState>>>;
+
+/// Root widget: routes to the active screen and slides it in on a change.
+pub struct Content {
+    menu: Screened,
+    grid: Screened,
+    results: Screened,
+    transition: Animate<()>,
+    current: Screen
+}
+impl Default for Content {
+    fn default() -> Self {
+        let theme = Theme::default();
+        Self {
+            menu: WidgetPod::new(Box::new(Menu::default())),
+            grid: WidgetPod::new(Box::new(Grid::default())),
+            results: WidgetPod::new(Box::new(Results::default())),
+            transition: Animate::new((), theme.transition_ease, theme.transition_dur),
+            current: Screen::Menu
+        }
+    }
+}
+impl Content {
+    /// The pod for the currently displayed screen.
+    fn active(&mut self) -> &mut Screened {
+        match self.current {
+            Screen::Menu => &mut self.menu,
+            Screen::Game => &mut self.grid,
+            Screen::Results => &mut self.results
+        }
+    }
+}
+impl Widget<AppState> for Content {
+    fn event(&mut self, ctx: &mut druid::EventCtx, event: &Event, data: &mut AppState, env: &druid::Env) {
+        if let &Event::AnimFrame(t) = event {
+            if !self.transition.finished() {
+                self.transition.anim_frame(t);
+                ctx.request_anim_frame();
+                ctx.request_paint();
+            }
+        }
+        self.active().event(ctx, event, data, env);
+    }
+    fn lifecycle(&mut self, ctx: &mut druid::LifeCycleCtx, event: &druid::LifeCycle, data: &AppState, env: &druid::Env) {
+        self.menu.lifecycle(ctx, event, data, env);
+        self.grid.lifecycle(ctx, event, data, env);
+        self.results.lifecycle(ctx, event, data, env);
+    }
+    fn update(&mut self, ctx: &mut druid::UpdateCtx, _: &AppState, data: &AppState, env: &druid::Env) {
+        if data.screen != self.current {
+            self.current = data.screen;
+            self.transition = Animate::new((), data.theme.transition_ease, data.theme.transition_dur);
+            ctx.request_anim_frame();
+        }
+        self.menu.update(ctx, data, env);
+        self.grid.update(ctx, data, env);
+        self.results.update(ctx, data, env);
+    }
+    fn layout(&mut self, ctx: &mut druid::LayoutCtx, bc: &druid::BoxConstraints, data: &AppState, env: &druid::Env) -> druid::Size {
+        let size = bc.max();
+        for pod in [&mut self.menu, &mut self.grid, &mut self.results] {
+            pod.layout(ctx, bc, data, env);
+            pod.set_origin(ctx, Point::ORIGIN);
+        }
+        size
+    }
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &AppState, env: &druid::Env) {
+        let dx = (1.0 - *self.transition) * ctx.size().width;
+        let active = self.active();
+        ctx.with_save(|ctx| {
+            ctx.transform(Affine::translate((dx, 0.0)));
+            active.paint(ctx, data, env);
+        });
+    }
+}